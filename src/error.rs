@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// Errors that can occur while loading the monitored file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be opened or read.
+    Io(std::io::Error),
+    /// The file was read but could not be deserialized in the selected format.
+    Parse(String),
+    /// The file deserialized fine but failed the caller-supplied validator.
+    Validation(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "failed to read config file: {err}"),
+            ConfigError::Parse(err) => write!(f, "failed to parse config file: {err}"),
+            ConfigError::Validation(err) => write!(f, "config failed validation: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
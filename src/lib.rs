@@ -2,27 +2,29 @@
 //! Automatically update your config when changes are made instead of restarting each time.
 //!
 //! # Example
-//! ```
+//! ```no_run
 //! use serde::Deserialize;
-//! use config_updater::ConfigMonitor;
+//! use config_updater::{ConfigMonitor, FileSource};
 //!
-//! #[derive(Deserialize)]
+//! #[derive(Deserialize, PartialEq)]
 //! struct MyConfig {
 //!     id: u64,
 //! }
 //!
-//! #[tokio::main]
+//! impl config_updater::HasReloadInterval for MyConfig {}
+//!
+//! #[tokio::main(flavor = "current_thread")]
 //! async fn main() {
-//!     let config_monitor: ConfigMonitor<MyConfig> = ConfigMonitor::new("./config.json", Some(30));
+//!     let config_monitor: ConfigMonitor<FileSource<MyConfig>> = ConfigMonitor::new("./config.json", Some(30));
 //!     let my_config = config_monitor.data(); // Arc<Mutex<MyConfig>>
 //!     let config_handle = config_monitor.monitor();
 //!
 //!     let c_my_config = my_config.clone();
-//!     tokio::spawn(async {
+//!     tokio::spawn(async move {
 //!         // Do Something with c_my_config
 //!         let my_id = {
 //!             let lock = c_my_config.lock().await;
-//!             lock.id.clone();
+//!             lock.id.clone()
 //!         };
 //!
 //!         println!("My ID: {}", my_id);
@@ -32,73 +34,519 @@
 //! }
 //! ```
 
-use std::fs::File;
+mod error;
+mod source;
+
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::io::BufReader;
-use std::time::{Duration, UNIX_EPOCH};
-use tokio::sync::Mutex;
+use std::sync::mpsc as std_mpsc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc as tokio_mpsc, Mutex};
 use tokio::task::JoinHandle;
-use log::info;
+use log::{info, warn};
+use notify::{RecursiveMode, Watcher};
 use serde::de::DeserializeOwned;
 use tokio::time::{Instant, sleep_until};
 
-pub struct ConfigMonitor<T>{
-    filename: String,
+pub use error::ConfigError;
+pub use source::{ConfigSource, FileSource, Format, Version};
+
+/// How a file-backed `ConfigMonitor` notices that the underlying file has changed.
+pub enum WatchMode {
+    /// Stat the file every `recheck_delay_seconds` and compare mtimes.
+    ///
+    /// Works everywhere, including network mounts where inotify-style events are
+    /// unreliable, at the cost of up to `recheck_delay_seconds` of reload lag.
+    Poll,
+    /// Watch the parent directory with the `notify` crate and reload as soon as a
+    /// write is observed, coalescing bursts of events within `debounce` into a
+    /// single reload.
+    Watch { debounce: Duration },
+}
+
+/// Capacity of the broadcast channel returned by [`ConfigMonitor::subscribe`].
+///
+/// Lagging subscribers just miss the oldest notifications and can re-lock
+/// `data()` to catch up, so this only needs to absorb a short burst of reloads.
+const RELOAD_NOTIFIER_CAPACITY: usize = 16;
+
+/// How often the file-watcher background thread checks whether its receiver
+/// was dropped, so it exits promptly after shutdown instead of only on the
+/// next filesystem event.
+const WATCHER_SHUTDOWN_POLL: Duration = Duration::from_secs(1);
+
+/// A config validator. Returning `Err` rejects a newly parsed config, keeping
+/// the previous value in place.
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// A human-friendly recheck interval for [`ConfigMonitor::new_with_interval`].
+/// Accepts a [`Duration`] directly, or a string parsed with `humantime`
+/// (`"30s"`, `"5m"`, `"1h"`).
+pub struct DurationSpec(Duration);
+
+impl DurationSpec {
+    /// Rounds up to whole seconds, with a floor of 1 — `recheck_delay_seconds`
+    /// has no notion of sub-second delay, and `0` would spin the monitor loop.
+    fn as_secs(&self) -> u64 {
+        let mut secs = self.0.as_secs();
+        if secs == 0 || self.0.subsec_nanos() > 0 {
+            secs += 1;
+        }
+        secs.max(1)
+    }
+}
+
+impl From<Duration> for DurationSpec {
+    fn from(duration: Duration) -> Self {
+        DurationSpec(duration)
+    }
+}
+
+impl From<&str> for DurationSpec {
+    /// Parses a human-friendly duration such as `"30s"`, `"5m"`, or `"1h"`.
+    ///
+    /// # Panics
+    /// Panics if `value` isn't a valid `humantime` duration.
+    fn from(value: &str) -> Self {
+        DurationSpec(humantime::parse_duration(value).expect("invalid duration"))
+    }
+}
+
+/// Lets a config type expose its own recheck interval, so operators can retune
+/// polling frequency by editing the config instead of restarting. Implement
+/// this (even with the default body) to opt in; `ConfigMonitor` re-reads it
+/// after every check and adjusts its own sleep duration on the fly.
+pub trait HasReloadInterval {
+    /// Returns `None` to leave the monitor's current interval unchanged.
+    fn reload_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+pub struct ConfigMonitor<S: ConfigSource> {
+    source: S,
     recheck_delay_seconds: u64,
-    data: Arc<Mutex<T>>,
+    validator: Option<Validator<S::Value>>,
+    data: Arc<Mutex<S::Value>>,
+    reload_notifier: broadcast::Sender<()>,
+    /// Fires to prompt an out-of-schedule check, from a file watcher, SIGHUP
+    /// listener, or both merged together. `None` means "poll only".
+    external_wake: Option<tokio_mpsc::UnboundedReceiver<()>>,
 }
 
-impl<T: DeserializeOwned + Send + 'static> ConfigMonitor<T> {
-    pub fn new(filename: &str, recheck_delay_seconds: Option<u64>) -> Self {
-        let data = Self::load_file(filename);
+impl<S> ConfigMonitor<S>
+where
+    S: ConfigSource + Send + 'static,
+    S::Value: PartialEq + Send + HasReloadInterval + 'static,
+{
+    /// Builds a monitor around any [`ConfigSource`], not just local files —
+    /// an HTTP endpoint, a KV store, or anything else that can report a cheap
+    /// [`Version`] token and `fetch` a fresh value.
+    pub async fn from_source(source: S, recheck_delay_seconds: Option<u64>) -> Result<Self, ConfigError> {
+        let data = source.fetch().await?;
+        Ok(Self::build(source, recheck_delay_seconds, data, None, None))
+    }
+
+    fn build(
+        source: S,
+        recheck_delay_seconds: Option<u64>,
+        data: S::Value,
+        validator: Option<Validator<S::Value>>,
+        external_wake: Option<tokio_mpsc::UnboundedReceiver<()>>,
+    ) -> Self {
+        let (reload_notifier, _) = broadcast::channel(RELOAD_NOTIFIER_CAPACITY);
 
         Self {
-            filename: filename.to_string(),
+            source,
             recheck_delay_seconds: recheck_delay_seconds.unwrap_or(300),
-            data: Arc::new(Mutex::new(data))
+            validator,
+            data: Arc::new(Mutex::new(data)),
+            reload_notifier,
+            external_wake,
         }
     }
 
-    pub fn data(&self) -> Arc<Mutex<T>> {
+    pub fn data(&self) -> Arc<Mutex<S::Value>> {
         self.data.clone()
     }
 
-    pub fn monitor(self) -> JoinHandle<()> {
+    /// Subscribes to reload notifications. A message is sent after every
+    /// successful reload, once the new value is visible through [`data`](Self::data),
+    /// so downstream tasks can re-derive caches or reconnect pools without
+    /// polling the data themselves.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.reload_notifier.subscribe()
+    }
+
+    pub fn monitor(mut self) -> JoinHandle<()> {
         let config_data = Arc::clone(&self.data);
+        let mut external_wake = self.external_wake.take();
+
         tokio::task::spawn(async move {
-            let mut file_last_modified = self.file_last_modified();
+            let mut last_version = self.source.version().await.ok();
 
             loop {
-                let file_recent_modified = self.file_last_modified();
-
-                if file_last_modified != file_recent_modified {
-                    info!("Found file changes, updating config...");
-                    file_last_modified = file_recent_modified;
-                    let data = Self::load_file(&self.filename);
-                    let mut lock = config_data.lock().await;
-                    *lock = data;
+                match external_wake.as_mut() {
+                    Some(wake) => tokio::select! {
+                        _ = sleep_until(Instant::now() + Duration::from_secs(self.recheck_delay_seconds)) => {}
+                        woken = wake.recv() => {
+                            if woken.is_none() {
+                                external_wake = None;
+                            }
+                        }
+                    },
+                    None => sleep_until(Instant::now() + Duration::from_secs(self.recheck_delay_seconds)).await,
                 }
 
-                sleep_until(Instant::now() + Duration::from_secs(self.recheck_delay_seconds)).await
+                self.check_for_changes(&config_data, &mut last_version).await;
+
+                if let Some(interval) = config_data.lock().await.reload_interval() {
+                    self.recheck_delay_seconds = interval.as_secs().max(1);
+                }
             }
         })
     }
 
-    fn load_file(filename: &str) -> T {
-        let file = File::open(filename).unwrap();
-        let reader = BufReader::new(file);
-        serde_json::from_reader(reader).unwrap()
+    /// Checks `self.source.version()` and only pays for a `fetch` when the
+    /// token changed. A transient error checking the version or fetching the
+    /// value, a failed validator, or an unchanged value all just keep the
+    /// previous value in place instead of panicking or notifying needlessly.
+    async fn check_for_changes(&self, config_data: &Arc<Mutex<S::Value>>, last_version: &mut Option<Version>) {
+        let version = match self.source.version().await {
+            Ok(version) => version,
+            Err(err) => {
+                warn!("Failed to check for config changes, keeping previous value: {err}");
+                return;
+            }
+        };
+
+        if last_version.as_ref() == Some(&version) {
+            return;
+        }
+        *last_version = Some(version);
+
+        info!("Found config changes, reloading...");
+        let data = match self.source.fetch().await {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("Failed to reload config, keeping previous value: {err}");
+                return;
+            }
+        };
+
+        if let Some(validate) = self.validator.as_deref() {
+            if let Err(err) = validate(&data).map_err(ConfigError::Validation) {
+                warn!("New config failed validation, keeping previous value: {err}");
+                return;
+            }
+        }
+
+        let mut lock = config_data.lock().await;
+        if *lock == data {
+            return;
+        }
+        *lock = data;
+        drop(lock);
+
+        let _ = self.reload_notifier.send(());
+    }
+}
+
+impl<T: DeserializeOwned + Send + Sync + PartialEq + HasReloadInterval + 'static> ConfigMonitor<FileSource<T>> {
+    pub fn new(filename: &str, recheck_delay_seconds: Option<u64>) -> Self {
+        Self::new_with_watch_mode(filename, recheck_delay_seconds, WatchMode::Poll)
+    }
+
+    /// Like [`new`](Self::new), but takes a human-friendly duration (`"30s"`,
+    /// `"5m"`, `"1h"`, or a [`Duration`]) instead of a raw second count.
+    pub fn new_with_interval(filename: &str, interval: impl Into<DurationSpec>) -> Self {
+        Self::new(filename, Some(interval.into().as_secs()))
+    }
+
+    pub fn new_with_watch_mode(filename: &str, recheck_delay_seconds: Option<u64>, watch_mode: WatchMode) -> Self {
+        Self::new_with_format(filename, recheck_delay_seconds, watch_mode, Format::Json)
+    }
+
+    /// Like [`new_with_watch_mode`](Self::new_with_watch_mode), but additionally lets
+    /// you pick the deserialization format instead of assuming JSON. Pass
+    /// [`Format::Auto`] to detect it from the file extension.
+    pub fn new_with_format(filename: &str, recheck_delay_seconds: Option<u64>, watch_mode: WatchMode, format: Format) -> Self {
+        Self::new_inner(filename, recheck_delay_seconds, watch_mode, format, None)
+    }
+
+    /// Like [`new_with_format`](Self::new_with_format), but additionally rejects a
+    /// newly parsed config (keeping the previous value) whenever `validator`
+    /// returns `Err`.
+    pub fn new_with_validator(
+        filename: &str,
+        recheck_delay_seconds: Option<u64>,
+        watch_mode: WatchMode,
+        format: Format,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self::new_inner(filename, recheck_delay_seconds, watch_mode, format, Some(Box::new(validator)))
+    }
+
+    /// Like [`new_with_watch_mode`](Self::new_with_watch_mode), but additionally lets
+    /// an operator trigger an immediate check with `kill -HUP <pid>` instead of
+    /// waiting for the poll interval or a filesystem event. The check is the same
+    /// one the poll runs: the file is re-read only if its mtime (in whole seconds)
+    /// differs from the last one seen, so a SIGHUP does not reload an unchanged file.
+    #[cfg(all(unix, feature = "sighup"))]
+    pub fn new_with_sighup(filename: &str, recheck_delay_seconds: Option<u64>, watch_mode: WatchMode, reload_on_sighup: bool) -> Self {
+        let mut monitor = Self::new_with_watch_mode(filename, recheck_delay_seconds, watch_mode);
+        if reload_on_sighup {
+            monitor.add_sighup_wake();
+        }
+        monitor
+    }
+
+    fn new_inner(filename: &str, recheck_delay_seconds: Option<u64>, watch_mode: WatchMode, format: Format, validator: Option<Validator<T>>) -> Self {
+        let source = FileSource::with_format(filename, format);
+        let data = source.load_sync().expect("failed to load initial config");
+        if let Some(validate) = validator.as_deref() {
+            validate(&data).map_err(ConfigError::Validation).expect("initial config failed validation");
+        }
+
+        let external_wake = match watch_mode {
+            WatchMode::Poll => None,
+            WatchMode::Watch { debounce } => Some(Self::watch_wake_channel(source.filename(), debounce)),
+        };
+
+        Self::build(source, recheck_delay_seconds, data, validator, external_wake)
     }
 
-    fn file_last_modified(&self) -> u64 {
-        let file = File::open(&self.filename).unwrap();
-        file
-            .metadata()
-            .unwrap()
-            .modified()
-            .unwrap()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
+    /// Merges in a `SIGHUP`-driven wake, alongside any existing file-watch wake.
+    #[cfg(all(unix, feature = "sighup"))]
+    fn add_sighup_wake(&mut self) {
+        let signal = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => signal,
+            Err(err) => {
+                warn!("Failed to register SIGHUP handler: {err}");
+                return;
+            }
+        };
+
+        let tx = match self.external_wake.take() {
+            Some(existing) => {
+                // Merge into a fresh channel: forward the existing receiver into it
+                // and let the SIGHUP forwarder feed it too.
+                let (tx, rx) = tokio_mpsc::unbounded_channel();
+                Self::forward_wakes(existing, tx.clone());
+                self.external_wake = Some(rx);
+                tx
+            }
+            None => {
+                let (tx, rx) = tokio_mpsc::unbounded_channel();
+                self.external_wake = Some(rx);
+                tx
+            }
+        };
+
+        Self::forward_sighup(signal, tx);
+    }
+
+    /// Forwards `from` into `to` until either side closes. Also re-checks `to`
+    /// every [`WATCHER_SHUTDOWN_POLL`] even with no wakes in flight, so this
+    /// task exits promptly once `monitor()` drops the merged receiver instead
+    /// of lingering until the next file-watch or SIGHUP wake.
+    #[cfg(all(unix, feature = "sighup"))]
+    fn forward_wakes(mut from: tokio_mpsc::UnboundedReceiver<()>, to: tokio_mpsc::UnboundedSender<()>) {
+        tokio::task::spawn(async move {
+            loop {
+                if to.is_closed() {
+                    break;
+                }
+                tokio::select! {
+                    woken = from.recv() => match woken {
+                        Some(()) => {
+                            if to.send(()).is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    },
+                    _ = tokio::time::sleep(WATCHER_SHUTDOWN_POLL) => {}
+                }
+            }
+        });
+    }
+
+    /// See [`forward_wakes`](Self::forward_wakes) for why this also polls `tx`
+    /// for closure instead of only reacting to incoming signals.
+    #[cfg(all(unix, feature = "sighup"))]
+    fn forward_sighup(mut signal: tokio::signal::unix::Signal, tx: tokio_mpsc::UnboundedSender<()>) {
+        tokio::task::spawn(async move {
+            loop {
+                if tx.is_closed() {
+                    break;
+                }
+                tokio::select! {
+                    woken = signal.recv() => {
+                        if woken.is_none() {
+                            break;
+                        }
+                        info!("Received SIGHUP, reloading config...");
+                        if tx.send(()).is_err() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(WATCHER_SHUTDOWN_POLL) => {}
+                }
+            }
+        });
+    }
+
+    /// Spawns a blocking thread that watches `filename`'s parent directory and
+    /// forwards one debounced wake per burst of events touching `filename`.
+    /// Editors often emit several events (rename, write, chmod) for a single
+    /// save, so events within `debounce` of each other collapse into one wake.
+    ///
+    /// Dropping or aborting the `monitor()` task closes `rx`, but since this
+    /// thread otherwise only wakes on filesystem events, it polls `tx` for
+    /// closure at least once every [`WATCHER_SHUTDOWN_POLL`] so the thread
+    /// exits promptly instead of lingering until the next unrelated write.
+    fn watch_wake_channel(filename: &str, debounce: Duration) -> tokio_mpsc::UnboundedReceiver<()> {
+        let (tx, rx) = tokio_mpsc::unbounded_channel();
+        let filename = PathBuf::from(filename);
+        let parent = filename.parent().filter(|p| !p.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        std::thread::spawn(move || {
+            let (std_tx, std_rx) = std_mpsc::channel();
+            let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                if let Ok(event) = res {
+                    let _ = std_tx.send(event);
+                }
+            }) {
+                Ok(watcher) => watcher,
+                Err(err) => {
+                    warn!("Failed to create config file watcher: {err}");
+                    return;
+                }
+            };
+
+            if let Err(err) = watcher.watch(&parent, RecursiveMode::NonRecursive) {
+                warn!("Failed to watch {}: {err}", parent.display());
+                return;
+            }
+
+            let is_match = |event: &notify::Event| event.paths.iter().any(|path| path.file_name() == filename.file_name());
+
+            loop {
+                match std_rx.recv_timeout(WATCHER_SHUTDOWN_POLL) {
+                    Ok(event) if is_match(&event) => {
+                        // Drain anything else arriving within the debounce window into one wake.
+                        while std_rx.recv_timeout(debounce).is_ok() {}
+                        if tx.send(()).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {
+                        if tx.is_closed() {
+                            break;
+                        }
+                    }
+                    Err(std_mpsc::RecvTimeoutError::Timeout) => {
+                        if tx.is_closed() {
+                            break;
+                        }
+                    }
+                    Err(std_mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestConfig {
+        value: u32,
+    }
+
+    impl HasReloadInterval for TestConfig {}
+
+    fn temp_config_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("config_updater_test_{name}_{}.json", std::process::id()))
+    }
+
+    #[test]
+    fn duration_spec_parses_human_friendly_strings() {
+        assert_eq!(DurationSpec::from("30s").as_secs(), 30);
+        assert_eq!(DurationSpec::from("5m").as_secs(), 300);
+        assert_eq!(DurationSpec::from("1h").as_secs(), 3600);
+    }
+
+    #[test]
+    fn duration_spec_floors_sub_second_durations_to_one() {
+        assert_eq!(DurationSpec::from("500ms").as_secs(), 1);
+        assert_eq!(DurationSpec::from(Duration::from_millis(0)).as_secs(), 1);
+    }
+
+    #[tokio::test]
+    async fn check_for_changes_skips_notify_when_value_is_unchanged() {
+        let path = temp_config_path("unchanged");
+        std::fs::write(&path, r#"{"value": 1}"#).unwrap();
+
+        let monitor = ConfigMonitor::<FileSource<TestConfig>>::new(path.to_str().unwrap(), None);
+        let data = monitor.data();
+        let mut subscriber = monitor.subscribe();
+
+        monitor.check_for_changes(&data, &mut None).await;
+
+        assert_eq!(data.lock().await.value, 1);
+        assert!(subscriber.try_recv().is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn check_for_changes_notifies_subscribers_when_value_changes() {
+        let path = temp_config_path("changed");
+        std::fs::write(&path, r#"{"value": 1}"#).unwrap();
+
+        let monitor = ConfigMonitor::<FileSource<TestConfig>>::new(path.to_str().unwrap(), None);
+        let data = monitor.data();
+        let mut subscriber = monitor.subscribe();
+
+        std::fs::write(&path, r#"{"value": 2}"#).unwrap();
+        monitor.check_for_changes(&data, &mut None).await;
+
+        assert_eq!(data.lock().await.value, 2);
+        assert!(subscriber.try_recv().is_ok());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn check_for_changes_keeps_previous_value_on_validation_failure() {
+        let path = temp_config_path("invalid");
+        std::fs::write(&path, r#"{"value": 1}"#).unwrap();
+
+        let monitor = ConfigMonitor::<FileSource<TestConfig>>::new_with_validator(
+            path.to_str().unwrap(),
+            None,
+            WatchMode::Poll,
+            Format::Json,
+            |config: &TestConfig| if config.value == 0 { Err("value must be non-zero".into()) } else { Ok(()) },
+        );
+        let data = monitor.data();
+        let mut subscriber = monitor.subscribe();
+
+        std::fs::write(&path, r#"{"value": 0}"#).unwrap();
+        monitor.check_for_changes(&data, &mut None).await;
+
+        assert_eq!(data.lock().await.value, 1);
+        assert!(subscriber.try_recv().is_err());
+
+        std::fs::remove_file(&path).ok();
     }
 }
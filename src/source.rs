@@ -0,0 +1,166 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use crate::error::ConfigError;
+
+/// A cheap, comparable token that changes whenever a [`ConfigSource`]'s value
+/// changes: a file's mtime, an HTTP `ETag`/`Last-Modified`, a KV revision, etc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version(String);
+
+impl Version {
+    pub fn new(token: impl Into<String>) -> Self {
+        Version(token.into())
+    }
+}
+
+/// Something `ConfigMonitor` can poll for changes and load a value from.
+///
+/// `version` should be cheap to call repeatedly (a HEAD request, an mtime stat),
+/// so the monitor only pays for the heavier `fetch` when the token changed.
+#[async_trait]
+pub trait ConfigSource: Send + Sync {
+    type Value: DeserializeOwned + Send;
+
+    async fn fetch(&self) -> Result<Self::Value, ConfigError>;
+    async fn version(&self) -> Result<Version, ConfigError>;
+}
+
+/// Deserialization format used by [`FileSource`].
+pub enum Format {
+    Json,
+    #[cfg(feature = "toml")]
+    Toml,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "ron")]
+    Ron,
+    /// Picks the format from the file's extension (`.json`, `.toml`, `.yml`/`.yaml`,
+    /// `.ron`), falling back to JSON if the extension is missing or unrecognised.
+    Auto,
+}
+
+impl Format {
+    fn detect(filename: &str) -> Format {
+        match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Format::Toml,
+            #[cfg(feature = "yaml")]
+            Some("yml") | Some("yaml") => Format::Yaml,
+            #[cfg(feature = "ron")]
+            Some("ron") => Format::Ron,
+            _ => Format::Json,
+        }
+    }
+}
+
+fn load_file<T: DeserializeOwned>(filename: &str, format: &Format) -> Result<T, ConfigError> {
+    match format {
+        Format::Auto => load_file(filename, &Format::detect(filename)),
+        Format::Json => {
+            let file = File::open(filename)?;
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader).map_err(|err| ConfigError::Parse(err.to_string()))
+        }
+        #[cfg(feature = "toml")]
+        Format::Toml => {
+            let contents = std::fs::read_to_string(filename)?;
+            toml::from_str(&contents).map_err(|err| ConfigError::Parse(err.to_string()))
+        }
+        #[cfg(feature = "yaml")]
+        Format::Yaml => {
+            let file = File::open(filename)?;
+            serde_yaml::from_reader(file).map_err(|err| ConfigError::Parse(err.to_string()))
+        }
+        #[cfg(feature = "ron")]
+        Format::Ron => {
+            let file = File::open(filename)?;
+            ron::de::from_reader(file).map_err(|err| ConfigError::Parse(err.to_string()))
+        }
+    }
+}
+
+/// The crate's built-in [`ConfigSource`]: a local file, deserialized with the
+/// given [`Format`] and versioned by its mtime.
+pub struct FileSource<T> {
+    filename: String,
+    format: Format,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> FileSource<T> {
+    pub fn new(filename: impl Into<String>) -> Self {
+        Self::with_format(filename, Format::Json)
+    }
+
+    pub fn with_format(filename: impl Into<String>, format: Format) -> Self {
+        Self {
+            filename: filename.into(),
+            format,
+            _value: PhantomData,
+        }
+    }
+
+    pub(crate) fn filename(&self) -> &str {
+        &self.filename
+    }
+}
+
+impl<T: DeserializeOwned> FileSource<T> {
+    pub(crate) fn load_sync(&self) -> Result<T, ConfigError> {
+        load_file(&self.filename, &self.format)
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send + Sync> ConfigSource for FileSource<T> {
+    type Value = T;
+
+    async fn fetch(&self) -> Result<T, ConfigError> {
+        self.load_sync()
+    }
+
+    async fn version(&self) -> Result<Version, ConfigError> {
+        let file = File::open(&self.filename)?;
+        let modified = file.metadata()?.modified()?;
+        let secs = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        Ok(Version::new(secs.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_json_by_default() {
+        assert!(matches!(Format::detect("config.json"), Format::Json));
+        assert!(matches!(Format::detect("config"), Format::Json));
+        assert!(matches!(Format::detect("config.ini"), Format::Json));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn detects_toml_extension() {
+        assert!(matches!(Format::detect("config.toml"), Format::Toml));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn detects_yaml_extension() {
+        assert!(matches!(Format::detect("config.yml"), Format::Yaml));
+        assert!(matches!(Format::detect("config.yaml"), Format::Yaml));
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn detects_ron_extension() {
+        assert!(matches!(Format::detect("config.ron"), Format::Ron));
+    }
+}